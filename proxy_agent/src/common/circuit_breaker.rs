@@ -0,0 +1,212 @@
+// Copyright (c) Microsoft Corporation
+// SPDX-License-Identifier: MIT
+
+//! A per-endpoint circuit breaker guarding the wire-server/key request paths so the
+//! monitor loop backs off instead of hammering an endpoint that is already failing.
+
+use crate::common::error::Error;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+struct EndpointState {
+    state: State,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl EndpointState {
+    fn new() -> Self {
+        Self {
+            state: State::Closed,
+            consecutive_failures: 0,
+            opened_at: None,
+        }
+    }
+}
+
+/// Tracks consecutive failures per endpoint and trips open after `failure_threshold`
+/// of them, rejecting calls immediately until `cooldown` has elapsed, at which point a
+/// single probe call is let through (half-open) to decide whether to close again.
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    endpoints: Mutex<HashMap<String, EndpointState>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold,
+            cooldown,
+            endpoints: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns `Err(Error::breaker(..))` if `endpoint` is currently open, or if a
+    /// half-open probe is already in flight. Otherwise the caller is free to make
+    /// the request — including the single probe that decides whether to close again.
+    pub fn before_call(&self, endpoint: &str) -> Result<(), Error> {
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let state = endpoints
+            .entry(endpoint.to_string())
+            .or_insert_with(EndpointState::new);
+
+        match state.state {
+            State::Closed => Ok(()),
+            // A probe is already outstanding; reject further calls until it resolves.
+            State::HalfOpen => Err(Error::breaker(endpoint.to_string())),
+            State::Open => {
+                let elapsed = state.opened_at.map(|t| t.elapsed()).unwrap_or_default();
+                if elapsed < self.cooldown {
+                    return Err(Error::breaker(endpoint.to_string()));
+                }
+                // Cooldown elapsed: admit exactly one probe request.
+                state.state = State::HalfOpen;
+                Ok(())
+            }
+        }
+    }
+
+    /// Call after a request to `endpoint` completes. Retryable errors (5xx, timeouts)
+    /// count toward tripping the breaker; non-retryable errors (4xx, auth) do not. A
+    /// half-open probe that fails with a retryable error reopens the breaker regardless
+    /// of the failure count; one that fails with a non-retryable error closes it.
+    pub fn record_result<T>(&self, endpoint: &str, result: &Result<T, Error>) {
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let state = endpoints
+            .entry(endpoint.to_string())
+            .or_insert_with(EndpointState::new);
+
+        match result {
+            Ok(_) => {
+                state.state = State::Closed;
+                state.consecutive_failures = 0;
+                state.opened_at = None;
+            }
+            Err(error) if error.is_retryable() => {
+                state.consecutive_failures += 1;
+                if state.state == State::HalfOpen || state.consecutive_failures >= self.failure_threshold
+                {
+                    state.state = State::Open;
+                    state.opened_at = Some(Instant::now());
+                }
+            }
+            Err(_) => {
+                // Non-retryable (4xx/auth) errors surface to the caller but don't trip the breaker.
+                if state.state == State::HalfOpen {
+                    state.state = State::Closed;
+                    state.consecutive_failures = 0;
+                }
+            }
+        }
+    }
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(DEFAULT_FAILURE_THRESHOLD, DEFAULT_COOLDOWN)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::error::{HyperErrorType, KeyErrorType};
+    use http::StatusCode;
+
+    fn server_error() -> Error {
+        Error::hyper(HyperErrorType::ServerError(
+            "testurl.com".to_string(),
+            StatusCode::from_u16(503).unwrap(),
+        ))
+    }
+
+    fn auth_error() -> Error {
+        Error::key(KeyErrorType::KeyResponse(
+            "validate".to_string(),
+            StatusCode::from_u16(401).unwrap(),
+        ))
+    }
+
+    #[test]
+    fn trips_open_after_threshold_retryable_failures() {
+        let breaker = CircuitBreaker::new(3, Duration::from_secs(60));
+        let endpoint = "wireserver/key";
+
+        for _ in 0..2 {
+            breaker.before_call(endpoint).unwrap();
+            breaker.record_result::<()>(endpoint, &Err(server_error()));
+        }
+        // Still below threshold: calls still allowed.
+        assert!(breaker.before_call(endpoint).is_ok());
+        breaker.record_result::<()>(endpoint, &Err(server_error()));
+
+        // Third consecutive failure trips the breaker.
+        let rejected = breaker.before_call(endpoint);
+        assert!(rejected.is_err());
+        assert!(!rejected.unwrap_err().is_retryable());
+    }
+
+    #[test]
+    fn auth_failures_do_not_trip_the_breaker() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        let endpoint = "wireserver/key";
+
+        for _ in 0..5 {
+            breaker.before_call(endpoint).unwrap();
+            breaker.record_result::<()>(endpoint, &Err(auth_error()));
+        }
+
+        assert!(breaker.before_call(endpoint).is_ok());
+    }
+
+    #[test]
+    fn half_opens_after_cooldown_and_closes_on_success() {
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(20));
+        let endpoint = "wireserver/key";
+
+        breaker.before_call(endpoint).unwrap();
+        breaker.record_result::<()>(endpoint, &Err(server_error()));
+        assert!(breaker.before_call(endpoint).is_err());
+
+        std::thread::sleep(Duration::from_millis(30));
+
+        // Cooldown elapsed: the next call is let through as the half-open probe.
+        breaker.before_call(endpoint).unwrap();
+        breaker.record_result(endpoint, &Ok::<_, Error>(()));
+
+        assert!(breaker.before_call(endpoint).is_ok());
+    }
+
+    #[test]
+    fn only_a_single_probe_is_admitted_while_half_open() {
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(20));
+        let endpoint = "wireserver/key";
+
+        breaker.before_call(endpoint).unwrap();
+        breaker.record_result::<()>(endpoint, &Err(server_error()));
+        assert!(breaker.before_call(endpoint).is_err());
+
+        std::thread::sleep(Duration::from_millis(30));
+
+        // The first call after cooldown is admitted as the probe...
+        breaker.before_call(endpoint).unwrap();
+        // ...but a concurrent second call is rejected until the probe resolves.
+        assert!(breaker.before_call(endpoint).is_err());
+
+        // A failed probe reopens the breaker immediately.
+        breaker.record_result::<()>(endpoint, &Err(server_error()));
+        assert!(breaker.before_call(endpoint).is_err());
+    }
+}
@@ -1,10 +1,26 @@
 // Copyright (c) Microsoft Corporation
 // SPDX-License-Identifier: MIT
 
+use crate::common::secret_bytes::SecretBytes;
 use http::{uri::InvalidUri, StatusCode};
 use std::error::Error as StdError;
 use std::fmt::Display;
 
+/// IO error kinds worth retrying: timeouts and transport hiccups. Everything else
+/// (`NotFound`, `PermissionDenied`, disk-full on a config write, ...) is permanent.
+fn is_transient_io_error(kind: std::io::ErrorKind) -> bool {
+    matches!(
+        kind,
+        std::io::ErrorKind::TimedOut
+            | std::io::ErrorKind::ConnectionReset
+            | std::io::ErrorKind::ConnectionAborted
+            | std::io::ErrorKind::ConnectionRefused
+            | std::io::ErrorKind::BrokenPipe
+            | std::io::ErrorKind::WouldBlock
+            | std::io::ErrorKind::Interrupted
+    )
+}
+
 #[derive(Debug)]
 pub struct Error(Box<ErrorType>);
 
@@ -40,6 +56,46 @@ impl Error {
     pub fn wire_server(error_type: WireServerErrorType, message: String) -> Self {
         Self::new(ErrorType::WireServer(error_type, message))
     }
+
+    pub fn breaker(endpoint: String) -> Self {
+        Self::new(ErrorType::Breaker(endpoint))
+    }
+
+    /// The HTTP status code carried by this error, if any.
+    pub fn status_code(&self) -> Option<StatusCode> {
+        match self.0.as_ref() {
+            ErrorType::Hyper(HyperErrorType::ServerError(_, status)) => Some(*status),
+            ErrorType::Key(KeyErrorType::KeyResponse(_, status)) => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// Whether retrying the same call is likely to succeed: timeouts, transport
+    /// failures, and 5xx responses are retryable; 4xx and breaker rejections are not.
+    /// An `IO` error is only retryable if its `io::ErrorKind` is itself transient —
+    /// a permanent failure like `NotFound` or `PermissionDenied` should not trip the
+    /// circuit breaker.
+    pub fn is_retryable(&self) -> bool {
+        match self.status_code() {
+            Some(status) => status.is_server_error(),
+            None => match self.0.as_ref() {
+                ErrorType::IO(_, error) => is_transient_io_error(error.kind()),
+                ErrorType::Hyper(HyperErrorType::Custom(_, _)) => true,
+                _ => false,
+            },
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.status_code() == Some(StatusCode::NOT_FOUND)
+    }
+
+    pub fn is_auth_failure(&self) -> bool {
+        matches!(
+            self.status_code(),
+            Some(StatusCode::UNAUTHORIZED) | Some(StatusCode::FORBIDDEN)
+        )
+    }
 }
 
 impl Display for Error {
@@ -48,7 +104,16 @@ impl Display for Error {
     }
 }
 
-impl StdError for Error {}
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self.0.as_ref() {
+            ErrorType::IO(_, error) => Some(error),
+            ErrorType::Hyper(HyperErrorType::Custom(_, error)) => Some(error),
+            ErrorType::Key(KeyErrorType::ParseKeyUrl(_, _, error)) => Some(error),
+            _ => None,
+        }
+    }
+}
 
 #[derive(Debug, thiserror::Error)]
 enum ErrorType {
@@ -69,6 +134,9 @@ enum ErrorType {
 
     #[error("Failed to parse URL {0} with error: {1}")]
     ParseUrl(String, String),
+
+    #[error("Circuit breaker is open for '{0}': rejecting call until the cooldown elapses")]
+    Breaker(String),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -96,15 +164,18 @@ pub enum WireServerErrorType {
 
     #[error("Shared config call to wire server failed")]
     SharedConfig,
+
+    #[error("No protocol version supported by both client {0:?} and server {1:?}")]
+    VersionMismatch(Vec<String>, Vec<String>),
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum KeyErrorType {
-    #[error("Key status validation failed with the error: {0}")]
-    KeyStatusValidation(String),
+    #[error("Key status validation of key {0} failed with the error: {1}")]
+    KeyStatusValidation(SecretBytes, String),
 
-    #[error("Failed to send {0} key with error: {1}")]
-    SendKeyRequest(String, String),
+    #[error("Failed to send key {0} with error: {1}")]
+    SendKeyRequest(SecretBytes, String),
 
     #[error("Failed to {0} key with status code: {1}")]
     KeyResponse(String, StatusCode),
@@ -139,12 +210,117 @@ mod test {
         );
 
         error = Error::key(KeyErrorType::SendKeyRequest(
-            "acquire".to_string(),
+            super::SecretBytes::new(b"acquire-key".to_vec()),
             error.to_string(),
         ));
         assert_eq!(
             error.to_string(),
-            "Key error: Failed to send acquire key with error: Telemetry call to wire server failed with the error: Invalid response"
+            "Key error: Failed to send key [redacted] with error: Telemetry call to wire server failed with the error: Invalid response"
+        );
+    }
+
+    #[test]
+    fn error_classification_test() {
+        let not_found = Error::hyper(super::HyperErrorType::ServerError(
+            "testurl.com".to_string(),
+            StatusCode::from_u16(404).unwrap(),
+        ));
+        assert!(not_found.is_not_found());
+        assert!(!not_found.is_retryable());
+        assert!(!not_found.is_auth_failure());
+        assert_eq!(not_found.status_code(), Some(StatusCode::NOT_FOUND));
+
+        let auth_failure = Error::key(KeyErrorType::KeyResponse(
+            "validate".to_string(),
+            StatusCode::from_u16(401).unwrap(),
+        ));
+        assert!(auth_failure.is_auth_failure());
+        assert!(!auth_failure.is_retryable());
+
+        let server_error = Error::hyper(super::HyperErrorType::ServerError(
+            "testurl.com".to_string(),
+            StatusCode::from_u16(503).unwrap(),
+        ));
+        assert!(server_error.is_retryable());
+        assert!(!server_error.is_not_found());
+        assert!(!server_error.is_auth_failure());
+
+        let breaker_open = Error::breaker("wireserver/key".to_string());
+        assert!(!breaker_open.is_retryable());
+        assert_eq!(breaker_open.status_code(), None);
+        assert_eq!(
+            breaker_open.to_string(),
+            "Circuit breaker is open for 'wireserver/key': rejecting call until the cooldown elapses"
+        );
+    }
+
+    #[test]
+    fn io_errors_are_retryable_only_when_transient() {
+        let timed_out = Error::io(
+            "reading goal state".to_string(),
+            std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out"),
+        );
+        assert!(timed_out.is_retryable());
+
+        let connection_reset = Error::io(
+            "reading goal state".to_string(),
+            std::io::Error::new(std::io::ErrorKind::ConnectionReset, "reset"),
+        );
+        assert!(connection_reset.is_retryable());
+
+        let not_found = Error::io(
+            "opening config".to_string(),
+            std::io::Error::new(std::io::ErrorKind::NotFound, "missing"),
+        );
+        assert!(!not_found.is_retryable());
+
+        let permission_denied = Error::io(
+            "writing config".to_string(),
+            std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied"),
+        );
+        assert!(!permission_denied.is_retryable());
+    }
+
+    #[test]
+    fn error_source_test() {
+        use std::error::Error as _;
+
+        let io_error = std::io::Error::new(std::io::ErrorKind::Other, "disk full");
+        let error = Error::io("writing config".to_string(), io_error);
+        let source = error.source().expect("IO error should carry a source");
+        assert_eq!(source.to_string(), "disk full");
+
+        let invalid_uri = "http://exa mple.com"
+            .parse::<http::Uri>()
+            .expect_err("URI with a space should fail to parse");
+        let error = Error::key(KeyErrorType::ParseKeyUrl(
+            "base".to_string(),
+            "path".to_string(),
+            invalid_uri,
+        ));
+        assert!(error.source().is_some());
+
+        // Variants that don't wrap an underlying error have no source.
+        let no_source = Error::wire_server(
+            WireServerErrorType::Telemetry,
+            "Invalid response".to_string(),
         );
+        assert!(no_source.source().is_none());
+    }
+
+    #[test]
+    fn key_errors_never_expose_raw_key_material() {
+        let key = super::SecretBytes::new(b"top-secret-key-bytes".to_vec());
+
+        let error = Error::key(KeyErrorType::KeyStatusValidation(
+            key.clone(),
+            "status was not Ready".to_string(),
+        ));
+        assert!(!error.to_string().contains("top-secret-key-bytes"));
+        assert!(error.to_string().contains("[redacted]"));
+
+        let error = Error::key(KeyErrorType::SendKeyRequest(key, "timed out".to_string()));
+        assert!(!error.to_string().contains("top-secret-key-bytes"));
+        assert!(error.to_string().contains("[redacted]"));
     }
 }
@@ -0,0 +1,177 @@
+// Copyright (c) Microsoft Corporation
+// SPDX-License-Identifier: MIT
+
+//! An embedded HTTP server exposing the agent's live state out-of-band: `/health`
+//! for liveness probes and `/status` for the full aggregate status.
+
+use crate::common::error::{Error, HyperErrorType};
+use http::{Request, Response, StatusCode};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Server};
+use proxy_agent_shared::proxy_agent_aggregate_status::{
+    GuestProxyAgentAggregateStatus, ModuleState, OverallState,
+};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Supplies the agent's current aggregate status; implemented by whatever owns the
+/// live state (typically the monitor loop).
+pub trait StatusProvider: Send + Sync {
+    fn current_status(&self) -> GuestProxyAgentAggregateStatus;
+}
+
+fn is_healthy(status: &GuestProxyAgentAggregateStatus) -> bool {
+    let proxy_agent_status = &status.proxyAgentStatus;
+    proxy_agent_status.status == OverallState::SUCCESS
+        && [
+            &proxy_agent_status.monitorStatus,
+            &proxy_agent_status.keyLatchStatus,
+            &proxy_agent_status.ebpfProgramStatus,
+            &proxy_agent_status.proxyListenerStatus,
+            &proxy_agent_status.telemetryLoggerStatus,
+        ]
+        .into_iter()
+        .all(|detail| detail.status == ModuleState::RUNNING)
+}
+
+async fn handle(
+    provider: Arc<dyn StatusProvider>,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let response = match request.uri().path() {
+        "/health" => {
+            let status = provider.current_status();
+            let code = if is_healthy(&status) {
+                StatusCode::OK
+            } else {
+                StatusCode::SERVICE_UNAVAILABLE
+            };
+            Response::builder().status(code).body(Body::empty())
+        }
+        "/status" => match serde_json::to_vec(&provider.current_status()) {
+            Ok(body) => Response::builder()
+                .status(StatusCode::OK)
+                .header("content-type", "application/json")
+                .body(Body::from(body)),
+            Err(_) => Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(Body::empty()),
+        },
+        _ => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty()),
+    };
+
+    Ok(response.unwrap_or_else(|_| Response::new(Body::empty())))
+}
+
+/// Serves `/health` and `/status` on `addr` until the process exits. Intended to run
+/// on its own background task alongside the monitor loop.
+pub async fn serve(addr: SocketAddr, provider: Arc<dyn StatusProvider>) -> Result<(), Error> {
+    let make_svc = make_service_fn(move |_conn| {
+        let provider = provider.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |req| handle(provider.clone(), req))) }
+    });
+
+    Server::bind(&addr)
+        .serve(make_svc)
+        .await
+        .map_err(|error| HyperErrorType::Custom("health server failed".to_string(), error))
+        .map_err(Error::hyper)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum HealthCheckError {
+    #[error("Failed to reach health endpoint at {0}: {1}")]
+    Request(String, String),
+
+    #[error("Health endpoint at {0} reported unhealthy with status code: {1}")]
+    Unhealthy(String, StatusCode),
+}
+
+/// A minimal blocking client for polling a [`serve`]d agent's `/health` endpoint,
+/// for use by orchestration and liveness probes that can't await.
+pub struct HealthClient {
+    base_url: String,
+}
+
+impl HealthClient {
+    pub fn new(base_url: String) -> Self {
+        Self { base_url }
+    }
+
+    /// Blocks the current thread for a single `/health` request, mapping any
+    /// non-200 response to a typed [`HealthCheckError`].
+    pub fn health(&self) -> Result<(), HealthCheckError> {
+        let url = format!("{}/health", self.base_url);
+        let runtime = tokio::runtime::Runtime::new()
+            .map_err(|error| HealthCheckError::Request(url.clone(), error.to_string()))?;
+
+        runtime.block_on(async {
+            let client = hyper::Client::new();
+            let uri: hyper::Uri = url
+                .parse()
+                .map_err(|error: http::uri::InvalidUri| {
+                    HealthCheckError::Request(url.clone(), error.to_string())
+                })?;
+            let response = client
+                .get(uri)
+                .await
+                .map_err(|error| HealthCheckError::Request(url.clone(), error.to_string()))?;
+
+            if response.status() == StatusCode::OK {
+                Ok(())
+            } else {
+                Err(HealthCheckError::Unhealthy(url.clone(), response.status()))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proxy_agent_shared::proxy_agent_aggregate_status::{ProxyAgentDetailStatus, ProxyAgentStatus};
+
+    fn status_with(overall: OverallState, module: ModuleState) -> GuestProxyAgentAggregateStatus {
+        let detail = ProxyAgentDetailStatus {
+            status: module,
+            message: "".to_string(),
+            states: None,
+        };
+        GuestProxyAgentAggregateStatus {
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            proxyAgentStatus: ProxyAgentStatus {
+                version: "1.0.0".to_string(),
+                status: overall,
+                monitorStatus: detail.clone(),
+                keyLatchStatus: detail.clone(),
+                ebpfProgramStatus: detail.clone(),
+                proxyListenerStatus: detail.clone(),
+                telemetryLoggerStatus: detail,
+                proxyConnectionsCount: 0,
+            },
+            proxyConnectionSummary: vec![],
+            failedAuthenticateSummary: vec![],
+        }
+    }
+
+    #[test]
+    fn healthy_requires_success_and_all_modules_running() {
+        let status = status_with(OverallState::SUCCESS, ModuleState::RUNNING);
+        assert!(is_healthy(&status));
+    }
+
+    #[test]
+    fn unhealthy_when_overall_state_is_not_success() {
+        let status = status_with(OverallState::ERROR, ModuleState::RUNNING);
+        assert!(!is_healthy(&status));
+    }
+
+    #[test]
+    fn unhealthy_when_any_module_is_not_running() {
+        let status = status_with(OverallState::SUCCESS, ModuleState::STOPPED);
+        assert!(!is_healthy(&status));
+    }
+}
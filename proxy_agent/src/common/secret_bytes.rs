@@ -0,0 +1,188 @@
+// Copyright (c) Microsoft Corporation
+// SPDX-License-Identifier: MIT
+
+//! A zeroizing, redacted container for key material so secret bytes never end up
+//! verbatim in `Debug` output, log lines, or error strings.
+
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use std::fmt::{self, Debug, Display};
+
+const REDACTED: &str = "[redacted]";
+
+/// The wire encoding used when (de)serializing a [`SecretBytes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretEncoding {
+    Base64,
+    Hex,
+}
+
+/// Key material that zeroizes its backing buffer on drop and never prints its
+/// contents: `Debug` and `Display` both render as `"[redacted]"`.
+#[derive(Clone, PartialEq, Eq, Default)]
+pub struct SecretBytes(Vec<u8>);
+
+impl SecretBytes {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn encode(&self, encoding: SecretEncoding) -> String {
+        match encoding {
+            SecretEncoding::Base64 => base64::encode(&self.0),
+            SecretEncoding::Hex => hex::encode(&self.0),
+        }
+    }
+
+    pub fn decode(encoded: &str, encoding: SecretEncoding) -> Result<Self, SecretBytesError> {
+        let bytes = match encoding {
+            SecretEncoding::Base64 => {
+                base64::decode(encoded).map_err(SecretBytesError::Base64)?
+            }
+            SecretEncoding::Hex => hex::decode(encoded).map_err(SecretBytesError::Hex)?,
+        };
+        Ok(Self(bytes))
+    }
+}
+
+impl Drop for SecretBytes {
+    fn drop(&mut self) {
+        for byte in self.0.iter_mut() {
+            // SAFETY: writes through a raw pointer so the zeroing isn't optimized
+            // away as a dead store to a buffer that's about to be freed.
+            unsafe { std::ptr::write_volatile(byte, 0) };
+        }
+    }
+}
+
+impl Debug for SecretBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl Display for SecretBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+// The `Serialize`/`Deserialize` impls below use base64; use `#[serde(with =
+// "secret_bytes::hex_encoding")]` on a field to opt into hex instead, matching
+// whatever encoding the wire format on the other end expects.
+
+impl Serialize for SecretBytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        base64_encoding::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretBytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        base64_encoding::deserialize(deserializer)
+    }
+}
+
+/// `#[serde(with = "secret_bytes::base64_encoding")]`: the default encoding, also
+/// used by the `Serialize`/`Deserialize` impls above.
+pub mod base64_encoding {
+    use super::{SecretBytes, SecretEncoding};
+    use serde::de::{self, Deserialize, Deserializer};
+    use serde::ser::Serializer;
+
+    pub fn serialize<S: Serializer>(value: &SecretBytes, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.encode(SecretEncoding::Base64))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SecretBytes, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        SecretBytes::decode(&encoded, SecretEncoding::Base64).map_err(de::Error::custom)
+    }
+}
+
+/// `#[serde(with = "secret_bytes::hex_encoding")]`: opt into hex on a `SecretBytes`
+/// field when the peer expects a hex-encoded wire format instead of base64.
+pub mod hex_encoding {
+    use super::{SecretBytes, SecretEncoding};
+    use serde::de::{self, Deserialize, Deserializer};
+    use serde::ser::Serializer;
+
+    pub fn serialize<S: Serializer>(value: &SecretBytes, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.encode(SecretEncoding::Hex))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SecretBytes, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        SecretBytes::decode(&encoded, SecretEncoding::Hex).map_err(de::Error::custom)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SecretBytesError {
+    #[error("Failed to base64-decode secret: {0}")]
+    Base64(base64::DecodeError),
+
+    #[error("Failed to hex-decode secret: {0}")]
+    Hex(hex::FromHexError),
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SecretBytes, SecretEncoding};
+
+    #[test]
+    fn debug_and_display_never_leak_contents() {
+        let secret = SecretBytes::new(b"super-secret-key".to_vec());
+        assert_eq!(format!("{secret:?}"), "[redacted]");
+        assert_eq!(format!("{secret}"), "[redacted]");
+    }
+
+    #[test]
+    fn base64_round_trip() {
+        let secret = SecretBytes::new(vec![1, 2, 3, 4, 5]);
+        let encoded = secret.encode(SecretEncoding::Base64);
+        let decoded = SecretBytes::decode(&encoded, SecretEncoding::Base64).unwrap();
+        assert_eq!(decoded.as_bytes(), secret.as_bytes());
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        let secret = SecretBytes::new(vec![0xDE, 0xAD, 0xBE, 0xEF]);
+        let encoded = secret.encode(SecretEncoding::Hex);
+        assert_eq!(encoded, "deadbeef");
+        let decoded = SecretBytes::decode(&encoded, SecretEncoding::Hex).unwrap();
+        assert_eq!(decoded.as_bytes(), secret.as_bytes());
+    }
+
+    #[test]
+    fn serde_round_trip_uses_base64_by_default() {
+        let secret = SecretBytes::new(vec![9, 8, 7]);
+        let json = serde_json::to_string(&secret).unwrap();
+        assert_eq!(json, format!("\"{}\"", secret.encode(SecretEncoding::Base64)));
+
+        let back: SecretBytes = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.as_bytes(), secret.as_bytes());
+    }
+
+    #[derive(serde_derive::Serialize, serde_derive::Deserialize)]
+    struct HexWrapped {
+        #[serde(with = "super::hex_encoding")]
+        key: SecretBytes,
+    }
+
+    #[test]
+    fn serde_with_hex_encoding_round_trips() {
+        let wrapped = HexWrapped {
+            key: SecretBytes::new(vec![0xDE, 0xAD, 0xBE, 0xEF]),
+        };
+        let json = serde_json::to_string(&wrapped).unwrap();
+        assert_eq!(json, "{\"key\":\"deadbeef\"}");
+
+        let back: HexWrapped = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.key.as_bytes(), wrapped.key.as_bytes());
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright (c) Microsoft Corporation
+// SPDX-License-Identifier: MIT
+
+//! Protocol version negotiation for the wire-server key-latch handshake, so the key
+//! and goal-state payloads can evolve without breaking older guests.
+
+use crate::common::error::{Error, WireServerErrorType};
+use serde_derive::{Deserialize, Serialize};
+
+/// Protocol versions this agent supports, preference order, newest first. The server
+/// walks its own preference list and picks the first entry that also appears here.
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2.1", "2.0", "1.0"];
+
+/// Sent by the client to start (or renew) the key-latch handshake.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VersionNegotiationRequest {
+    pub supported_versions: Vec<String>,
+    /// Empty on the first handshake; echoed back by the client on every renewal.
+    pub client_id: String,
+}
+
+impl VersionNegotiationRequest {
+    /// The first handshake of a session: no `client_id` has been issued yet.
+    pub fn initial() -> Self {
+        Self {
+            supported_versions: supported_versions(),
+            client_id: String::new(),
+        }
+    }
+
+    /// A subsequent handshake, echoing back the `client_id` issued by the server.
+    pub fn renew(client_id: String) -> Self {
+        Self {
+            supported_versions: supported_versions(),
+            client_id,
+        }
+    }
+}
+
+fn supported_versions() -> Vec<String> {
+    SUPPORTED_PROTOCOL_VERSIONS
+        .iter()
+        .map(|v| v.to_string())
+        .collect()
+}
+
+/// Picks the highest mutually supported protocol version. `server_versions` is
+/// expected in preference order (newest first); the first entry it shares with
+/// `client_versions` wins.
+pub fn negotiate(client_versions: &[String], server_versions: &[String]) -> Result<String, Error> {
+    server_versions
+        .iter()
+        .find(|version| client_versions.contains(version))
+        .cloned()
+        .ok_or_else(|| {
+            Error::wire_server(
+                WireServerErrorType::VersionMismatch(
+                    client_versions.to_vec(),
+                    server_versions.to_vec(),
+                ),
+                "no overlapping protocol version between client and server".to_string(),
+            )
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn negotiates_the_highest_shared_server_preferred_version() {
+        let client_versions = vec!["2.0".to_string(), "1.0".to_string()];
+        let server_versions = vec!["2.1".to_string(), "2.0".to_string(), "1.0".to_string()];
+
+        let negotiated = negotiate(&client_versions, &server_versions).unwrap();
+        assert_eq!(negotiated, "2.0");
+    }
+
+    #[test]
+    fn fails_with_version_mismatch_when_no_overlap() {
+        let client_versions = vec!["0.9".to_string()];
+        let server_versions = vec!["2.1".to_string(), "2.0".to_string()];
+
+        let error = negotiate(&client_versions, &server_versions).unwrap_err();
+        assert!(error.to_string().contains("0.9"));
+        assert!(error.to_string().contains("2.1"));
+    }
+
+    #[test]
+    fn initial_request_has_no_client_id() {
+        let request = VersionNegotiationRequest::initial();
+        assert!(request.client_id.is_empty());
+        assert_eq!(request.supported_versions, supported_versions());
+    }
+
+    #[test]
+    fn renewed_request_echoes_client_id() {
+        let request = VersionNegotiationRequest::renew("client-123".to_string());
+        assert_eq!(request.client_id, "client-123");
+    }
+}
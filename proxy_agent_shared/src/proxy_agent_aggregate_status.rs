@@ -3,6 +3,30 @@
 use serde_derive::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Magic byte prefixing every MessagePack-encoded status payload, so a reader can
+/// tell the format apart from raw JSON before attempting to decode it.
+const MSGPACK_MAGIC: u8 = 0xA5;
+/// Schema version of the MessagePack payload that follows the magic byte.
+const MSGPACK_SCHEMA_VERSION: u8 = 1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum StatusEncodingError {
+    #[error("Failed to encode status as MessagePack: {0}")]
+    Encode(rmp_serde::encode::Error),
+
+    #[error("Failed to decode status as MessagePack: {0}")]
+    Decode(rmp_serde::decode::Error),
+
+    #[error("MessagePack status payload is too short to contain a header")]
+    MissingHeader,
+
+    #[error("Unrecognized MessagePack status header: expected magic byte {0:#x}, found {1:#x}")]
+    BadMagic(u8, u8),
+
+    #[error("Unsupported MessagePack status schema version: {0}, expected {1}")]
+    UnsupportedVersion(u8, u8),
+}
+
 #[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
 pub enum ModuleState {
     UNKNOWN,
@@ -17,7 +41,7 @@ pub enum OverallState {
     UNKNOWN,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
 #[allow(non_snake_case)]
 pub struct ProxyAgentDetailStatus {
     pub status: ModuleState, // ModuleState, RUNNING|STOPPED
@@ -26,7 +50,7 @@ pub struct ProxyAgentDetailStatus {
     pub states: Option<HashMap<String, String>>, // module specific states
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[allow(non_snake_case)]
 pub struct ProxyAgentStatus {
     pub version: String,
@@ -39,7 +63,7 @@ pub struct ProxyAgentStatus {
     pub proxyConnectionsCount: u128,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[allow(non_snake_case)]
 pub struct ProxyConnectionSummary {
     pub userName: String,
@@ -67,7 +91,7 @@ impl Clone for ProxyConnectionSummary {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[allow(non_snake_case)]
 pub struct GuestProxyAgentAggregateStatus {
     pub timestamp: String,
@@ -75,3 +99,106 @@ pub struct GuestProxyAgentAggregateStatus {
     pub proxyConnectionSummary: Vec<ProxyConnectionSummary>,
     pub failedAuthenticateSummary: Vec<ProxyConnectionSummary>,
 }
+
+impl GuestProxyAgentAggregateStatus {
+    /// Encodes `self` as MessagePack, prefixed with a magic byte and schema version
+    /// so a reader can tell the format and version apart before decoding the body.
+    pub fn to_msgpack(&self) -> Result<Vec<u8>, StatusEncodingError> {
+        let mut buffer = vec![MSGPACK_MAGIC, MSGPACK_SCHEMA_VERSION];
+        rmp_serde::encode::write_named(&mut buffer, self).map_err(StatusEncodingError::Encode)?;
+        Ok(buffer)
+    }
+
+    /// Decodes a payload produced by [`Self::to_msgpack`], validating the magic byte
+    /// and schema version before handing the remaining bytes to `rmp_serde`.
+    pub fn from_msgpack(data: &[u8]) -> Result<Self, StatusEncodingError> {
+        let [magic, version, body @ ..] = data else {
+            return Err(StatusEncodingError::MissingHeader);
+        };
+        if *magic != MSGPACK_MAGIC {
+            return Err(StatusEncodingError::BadMagic(MSGPACK_MAGIC, *magic));
+        }
+        if *version != MSGPACK_SCHEMA_VERSION {
+            return Err(StatusEncodingError::UnsupportedVersion(
+                *version,
+                MSGPACK_SCHEMA_VERSION,
+            ));
+        }
+        rmp_serde::from_slice(body).map_err(StatusEncodingError::Decode)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample_status() -> GuestProxyAgentAggregateStatus {
+        let detail = ProxyAgentDetailStatus {
+            status: ModuleState::RUNNING,
+            message: "ok".to_string(),
+            states: None,
+        };
+        GuestProxyAgentAggregateStatus {
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            proxyAgentStatus: ProxyAgentStatus {
+                version: "1.0.0".to_string(),
+                status: OverallState::SUCCESS,
+                monitorStatus: detail.clone(),
+                keyLatchStatus: detail.clone(),
+                ebpfProgramStatus: detail.clone(),
+                proxyListenerStatus: detail.clone(),
+                telemetryLoggerStatus: detail,
+                proxyConnectionsCount: 42,
+            },
+            proxyConnectionSummary: vec![ProxyConnectionSummary {
+                userName: "root".to_string(),
+                ip: "127.0.0.1".to_string(),
+                port: 8080,
+                processCmdLine: "curl".to_string(),
+                responseStatus: "200".to_string(),
+                count: 1,
+                userGroups: None,
+                processFullPath: None,
+            }],
+            failedAuthenticateSummary: vec![],
+        }
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let status = sample_status();
+        let json = serde_json::to_string(&status).unwrap();
+        let decoded: GuestProxyAgentAggregateStatus = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, status);
+    }
+
+    #[test]
+    fn msgpack_round_trip() {
+        let status = sample_status();
+        let encoded = status.to_msgpack().unwrap();
+        assert_eq!(encoded[0], MSGPACK_MAGIC);
+        assert_eq!(encoded[1], MSGPACK_SCHEMA_VERSION);
+
+        let decoded = GuestProxyAgentAggregateStatus::from_msgpack(&encoded).unwrap();
+        assert_eq!(decoded, status);
+    }
+
+    #[test]
+    fn msgpack_rejects_bad_magic() {
+        let mut encoded = sample_status().to_msgpack().unwrap();
+        encoded[0] = 0x00;
+        let result = GuestProxyAgentAggregateStatus::from_msgpack(&encoded);
+        assert!(matches!(result, Err(StatusEncodingError::BadMagic(_, _))));
+    }
+
+    #[test]
+    fn msgpack_rejects_unsupported_version() {
+        let mut encoded = sample_status().to_msgpack().unwrap();
+        encoded[1] = 0xFF;
+        let result = GuestProxyAgentAggregateStatus::from_msgpack(&encoded);
+        assert!(matches!(
+            result,
+            Err(StatusEncodingError::UnsupportedVersion(_, _))
+        ));
+    }
+}